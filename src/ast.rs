@@ -3,6 +3,8 @@
 use core::mem::discriminant;
 use std::fmt;
 
+use serde::Serialize;
+
 use crate::{
     function::{FunctionId, Signature},
     lex::{CodeSpan, Sp},
@@ -10,8 +12,42 @@ use crate::{
     Ident, Primitive,
 };
 
+/// A syntax node that covers a span of code
+pub trait Spanned {
+    /// Get the span covered by this node
+    fn span(&self) -> CodeSpan;
+}
+
+impl<T> Spanned for Sp<T> {
+    fn span(&self) -> CodeSpan {
+        self.span.clone()
+    }
+}
+
+impl Spanned for Item {
+    fn span(&self) -> CodeSpan {
+        Item::span(self)
+    }
+}
+
+impl Spanned for Binding {
+    fn span(&self) -> CodeSpan {
+        Binding::span(self)
+    }
+}
+
+impl Spanned for Modified {
+    fn span(&self) -> CodeSpan {
+        let span = self.modifier.span.clone();
+        match self.operands.last() {
+            Some(last) => span.merge(last.span.clone()),
+            None => span,
+        }
+    }
+}
+
 /// A top-level item
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Item {
     /// Just some code
     Words(Vec<Vec<Sp<Word>>>),
@@ -42,12 +78,20 @@ impl Item {
     }
 }
 
+/// Serialize parsed items to JSON
+///
+/// Spanned nodes are serialized with their `start` and `end` byte offsets.
+pub fn ast_to_json(items: &[Item]) -> String {
+    serde_json::to_string(items).expect("AST serialization cannot fail")
+}
+
 /// A binding
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Binding {
     /// The name of the binding
     pub name: Sp<Ident>,
     /// The span of the arrow
+    #[serde(skip)]
     pub arrow_span: CodeSpan,
     /// The signature
     pub signature: Option<Sp<Signature>>,
@@ -67,7 +111,7 @@ impl Binding {
 }
 
 /// A word
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 #[allow(missing_docs)]
 pub enum Word {
     Number(String, f64),
@@ -192,7 +236,7 @@ impl fmt::Debug for Word {
 }
 
 /// A stack array notation term
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Arr {
     /// The words in the array
     pub lines: Vec<Vec<Sp<Word>>>,
@@ -215,9 +259,10 @@ impl fmt::Debug for Arr {
 }
 
 /// An inline function
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Func {
     /// The function's id
+    #[serde(skip)]
     pub id: FunctionId,
     /// The function's signature
     pub signature: Option<Sp<Signature>>,
@@ -241,7 +286,7 @@ impl fmt::Debug for Func {
 }
 
 /// A switch function
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Switch {
     /// The branches of the switch
     pub branches: Vec<Sp<Func>>,
@@ -250,7 +295,7 @@ pub struct Switch {
 }
 
 /// A modifier with operands
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Modified {
     /// The modifier itself
     pub modifier: Sp<Modifier>,
@@ -276,7 +321,7 @@ impl fmt::Debug for Modified {
 }
 
 /// A modifier
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize)]
 pub enum Modifier {
     /// A primitive modifier
     Primitive(Primitive),
@@ -311,3 +356,50 @@ impl Modifier {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse::parse, Inputs};
+
+    #[test]
+    fn ast_json() {
+        let (items, errors, _) = parse("X ← +1\n[X 2]", (), &mut Inputs::default());
+        assert!(errors.is_empty());
+        let json: serde_json::Value = serde_json::from_str(&ast_to_json(&items)).unwrap();
+        let binding = &json[0]["Binding"];
+        assert_eq!(binding["name"]["value"], "X");
+        assert_eq!(binding["name"]["start"], 0);
+        assert_eq!(binding["name"]["end"], 1);
+        assert_eq!(binding["words"][0]["value"]["Primitive"], "add");
+        let array = json[1]["Words"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|line| line.as_array().unwrap())
+            .next()
+            .unwrap();
+        assert_eq!(array["value"]["Array"]["boxes"], false);
+        assert_eq!(array["start"], 9);
+        assert_eq!(array["end"], 14);
+    }
+
+    #[test]
+    fn modified_span() {
+        let code = "⊃+×";
+        let (items, errors, _) = parse(code, (), &mut Inputs::default());
+        assert!(errors.is_empty());
+        let Some(Item::Words(lines)) = items.first() else {
+            panic!("Expected words, found {items:?}");
+        };
+        let word = &lines[0][0];
+        let Word::Modified(m) = &word.value else {
+            panic!("Expected a modified word, found {word:?}");
+        };
+        let span = Spanned::span(&**m);
+        assert_eq!(span.start, m.modifier.span.start);
+        assert_eq!(span.end, m.operands.last().unwrap().span.end);
+        assert_eq!(span.byte_range(), 0..code.len());
+        assert_eq!(span, Spanned::span(word));
+    }
+}
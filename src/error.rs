@@ -4,7 +4,7 @@ use colored::*;
 
 use crate::{
     function::FunctionId,
-    lex::{Sp, Span},
+    lex::{CodeSpan, Sp, Span},
     parse::ParseError,
     value::Value,
     InputSrc, Inputs,
@@ -358,6 +358,32 @@ impl Report {
             color: true,
         }
     }
+    /// Create a report for all of the errors from a parse
+    ///
+    /// The errors are sorted by location, and errors with the same span and message
+    /// are only reported once. The report ends with the number of errors.
+    pub fn parse_errors(errors: &[Sp<ParseError>], inputs: &Inputs) -> Self {
+        let mut errors: Vec<(CodeSpan, String)> = (errors.iter())
+            .map(|error| (error.span.clone(), error.value.to_string()))
+            .collect();
+        errors.sort();
+        errors.dedup();
+        let kind = ReportKind::Error;
+        let mut fragments = Vec::new();
+        for (span, message) in &errors {
+            let report = Report::new_multi(kind, inputs, [(message, span.clone().into())]);
+            fragments.extend(report.fragments);
+            fragments.push(ReportFragment::Newline);
+            fragments.push(ReportFragment::Newline);
+        }
+        let s = if errors.len() == 1 { "" } else { "s" };
+        fragments.push(ReportFragment::Plain(format!("{} error{s}", errors.len())));
+        Self {
+            kind,
+            fragments,
+            color: true,
+        }
+    }
     /// Create a new report with multiple messages
     pub fn new_multi<I, T>(kind: ReportKind, inputs: &Inputs, errors: I) -> Self
     where
@@ -392,23 +418,35 @@ impl Report {
                 let line_prefix = format!("{} | ", span.start.line);
                 fragments.push(ReportFragment::Plain(line_prefix.clone()));
                 let input = inputs.get(&span.src);
-                let line = input
-                    .lines()
-                    .nth(span.start.line as usize - 1)
+                // Columns may count a tab as several characters,
+                // so find the characters to color from the byte positions
+                let line_start = (input.get(..span.start.byte_pos as usize))
+                    .and_then(|before| before.rfind('\n'))
+                    .map_or(0, |i| i + 1);
+                let line = (input.get(line_start..))
+                    .and_then(|rest| rest.lines().next())
                     .unwrap_or("");
-                let start_char_pos = span.start.col - 1;
+                let chars_until = |byte_pos: u32| {
+                    (input.get(line_start..byte_pos as usize)).map_or(0, |s| s.chars().count())
+                };
+                let start_char_pos = chars_until(span.start.byte_pos);
                 let end_char_pos = if span.start.line == span.end.line {
-                    span.end.col - 1
+                    chars_until(span.end.byte_pos)
                 } else {
-                    line.chars().count() as u16
-                };
-                let pre_color: String = line.chars().take(start_char_pos as usize).collect();
+                    line.chars().count()
+                }
+                .max(start_char_pos);
+                let pre_color: String = line.chars().take(start_char_pos).collect();
                 let color: String = line
                     .chars()
-                    .skip(start_char_pos as usize)
-                    .take((end_char_pos - start_char_pos) as usize)
+                    .skip(start_char_pos)
+                    .take(end_char_pos - start_char_pos)
+                    .collect();
+                let post_color: String = line.chars().skip(end_char_pos).collect();
+                // Keep tabs so the underline lines up with the code above it
+                let indent: String = (pre_color.chars())
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
                     .collect();
-                let post_color: String = line.chars().skip(end_char_pos as usize).collect();
                 fragments.push(ReportFragment::Faint(pre_color));
                 fragments.push(ReportFragment::Colored(color));
                 fragments.push(ReportFragment::Faint(post_color));
@@ -416,9 +454,9 @@ impl Report {
                 fragments.push(ReportFragment::Plain(
                     " ".repeat(line_prefix.chars().count()),
                 ));
-                fragments.push(ReportFragment::Plain(" ".repeat(start_char_pos as usize)));
+                fragments.push(ReportFragment::Plain(indent));
                 fragments.push(ReportFragment::Colored(
-                    "─".repeat((end_char_pos - start_char_pos) as usize),
+                    "─".repeat(end_char_pos - start_char_pos),
                 ));
             }
         }
@@ -460,3 +498,48 @@ impl fmt::Display for Report {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lex::lex_with_tab_width,
+        parse::{parse, parse_tokens},
+    };
+
+    #[test]
+    fn report_all_parse_errors() {
+        let mut inputs = Inputs::default();
+        let (_, mut errs, _) = parse("1 \"\\q\" 2\n3 @\\z", (), &mut inputs);
+        assert_eq!(errs.len(), 2);
+        // Out of order and duplicated
+        errs.reverse();
+        errs.push(errs[0].clone());
+        let report = Report::parse_errors(&errs, &inputs)
+            .color(false)
+            .to_string();
+        let first = report.find("1 | 1 \"\\q\" 2").unwrap();
+        let second = report.find("2 | 3 @\\z").unwrap();
+        assert!(first < second, "{report}");
+        assert_eq!(report.matches("Error: ").count(), 2, "{report}");
+        assert!(report.contains("\n\nError: "), "{report}");
+        assert!(report.ends_with("\n\n2 errors"), "{report}");
+    }
+
+    #[test]
+    fn tabbed_report_spans() {
+        // Columns count each tab as 8, but the underline is placed by character
+        for (input, underline) in [
+            ("\t\t\t(1 2\n3", "    \t\t\t────"),
+            ("\t+ 1)", "    \t   ─"),
+        ] {
+            let mut inputs = Inputs::default();
+            let (tokens, _) = lex_with_tab_width(input, (), &mut inputs, 8);
+            let (_, errs, _) = parse_tokens(tokens, &mut inputs);
+            let report = Report::parse_errors(&errs, &inputs)
+                .color(false)
+                .to_string();
+            assert!(report.lines().any(|line| line == underline), "{report}");
+        }
+    }
+}
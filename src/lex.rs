@@ -18,6 +18,21 @@ pub fn lex(
     input: &str,
     src: impl IntoInputSrc,
     inputs: &mut Inputs,
+) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>) {
+    lex_with_tab_width(input, src, inputs, 1)
+}
+
+/// Lex a Uiua source file, counting each tab as `tab_width` columns
+///
+/// This only affects the `col` of each [`Loc`], so that columns match editors
+/// that render tabs as multiple columns. Byte and char positions are unaffected.
+///
+/// The tokens can be parsed with [`parse_tokens`](crate::parse_tokens).
+pub fn lex_with_tab_width(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &mut Inputs,
+    tab_width: u16,
 ) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>) {
     let src = inputs.add_src(src, input);
     Lexer {
@@ -31,6 +46,7 @@ pub fn lex(
         src,
         tokens: Vec::new(),
         errors: Vec::new(),
+        tab_width,
     }
     .run()
 }
@@ -42,6 +58,7 @@ pub enum LexError {
     UnexpectedChar(String),
     ExpectedCharacter(Vec<char>),
     InvalidEscape(String),
+    InvalidCodePoint(u32),
     ExpectedNumber,
 }
 
@@ -60,6 +77,9 @@ impl fmt::Display for LexError {
             }
             LexError::ExpectedCharacter(chars) => write!(f, "Expected one of {:?}", chars),
             LexError::InvalidEscape(c) => write!(f, "Invalid escape character {c:?}"),
+            LexError::InvalidCodePoint(code) => {
+                write!(f, "Invalid escaped code point U+{code:04X}")
+            }
             LexError::ExpectedNumber => write!(f, "Expected number"),
         }
     }
@@ -330,6 +350,17 @@ pub struct Sp<T, S = CodeSpan> {
     pub span: S,
 }
 
+impl<T: Serialize> Serialize for Sp<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::SerializeStruct;
+        let mut sp = serializer.serialize_struct("Sp", 3)?;
+        sp.serialize_field("value", &self.value)?;
+        sp.serialize_field("start", &self.span.start.byte_pos)?;
+        sp.serialize_field("end", &self.span.end.byte_pos)?;
+        sp.end()
+    }
+}
+
 impl<T> Sp<T> {
     /// Map the value
     pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Sp<U> {
@@ -518,6 +549,7 @@ struct Lexer<'a> {
     src: InputSrc,
     tokens: Vec<Sp<Token>>,
     errors: Vec<Sp<LexError>>,
+    tab_width: u16,
 }
 
 impl<'a> Lexer<'a> {
@@ -532,7 +564,8 @@ impl<'a> Lexer<'a> {
                     self.loc.col = 1;
                 }
                 '\r' => {}
-                _ => self.loc.col += 1,
+                '\t' => self.loc.col = self.loc.col.saturating_add(self.tab_width),
+                _ => self.loc.col = self.loc.col.saturating_add(1),
             }
         }
         self.loc.char_pos += 1;
@@ -693,8 +726,7 @@ impl<'a> Lexer<'a> {
                             continue;
                         }
                         Err(e) => {
-                            self.errors
-                                .push(self.end_span(start).sp(LexError::InvalidEscape(e.into())));
+                            self.errors.push(self.end_span(start).sp(e));
                             continue;
                         }
                     };
@@ -902,12 +934,13 @@ impl<'a> Lexer<'a> {
         &mut self,
         escaped: &mut bool,
         escape_char: Option<char>,
-    ) -> Result<Option<String>, &'a str> {
+    ) -> Result<Option<String>, LexError> {
         let Some(c) =
             self.next_char_if_all(|c| !"\r\n".contains(c) && (Some(c) != escape_char || *escaped))
         else {
             return Ok(None);
         };
+        let invalid = |c: &str| LexError::InvalidEscape(c.into());
         Ok(Some(if *escaped {
             *escaped = false;
             match c {
@@ -926,20 +959,23 @@ impl<'a> Lexer<'a> {
                     for _ in 0..2 {
                         let c = self
                             .next_char_if_all(|c| c.is_ascii_hexdigit())
-                            .ok_or("x")?;
+                            .ok_or_else(|| invalid("x"))?;
                         code = code << 4 | c.chars().next().unwrap().to_digit(16).unwrap();
                     }
-                    std::char::from_u32(code).ok_or("x")?.into()
+                    std::char::from_u32(code)
+                        .ok_or_else(|| invalid("x"))?
+                        .into()
                 }
                 "u" => {
                     let mut code = 0;
-                    match self.peek_char().ok_or("u")? {
+                    match self.peek_char().ok_or_else(|| invalid("u"))? {
                         "{" => {
-                            self.next_char_if(|c| c == "{").ok_or("u")?;
+                            self.next_char_if(|c| c == "{")
+                                .ok_or_else(|| invalid("u"))?;
                             for _ in 0..7 {
                                 match self
                                     .next_char_if_all(|c| c.is_ascii_hexdigit() || c == '}')
-                                    .ok_or("u")?
+                                    .ok_or_else(|| invalid("u"))?
                                 {
                                     "}" => break,
                                     c => {
@@ -953,14 +989,16 @@ impl<'a> Lexer<'a> {
                             for _ in 0..4 {
                                 let c = self
                                     .next_char_if_all(|c| c.is_ascii_hexdigit())
-                                    .ok_or("u")?;
+                                    .ok_or_else(|| invalid("u"))?;
                                 code = code << 4 | c.chars().next().unwrap().to_digit(16).unwrap();
                             }
                         }
                     }
-                    std::char::from_u32(code).ok_or("u")?.into()
+                    std::char::from_u32(code)
+                        .ok_or(LexError::InvalidCodePoint(code))?
+                        .into()
                 }
-                c => return Err(c),
+                c => return Err(invalid(c)),
             }
         } else if c == "\\" {
             *escaped = true;
@@ -976,10 +1014,7 @@ impl<'a> Lexer<'a> {
             match self.character(&mut escaped, escape_char) {
                 Ok(Some(c)) => string.push_str(&c),
                 Ok(None) => break,
-                Err(e) => {
-                    self.errors
-                        .push(self.end_span(start).sp(LexError::InvalidEscape(e.into())));
-                }
+                Err(e) => self.errors.push(self.end_span(start).sp(e)),
             }
         }
         string
@@ -1021,3 +1056,42 @@ pub fn is_custom_glyph(c: &str) -> bool {
             .all(|c| !c.is_ascii() && !is_ident_char(c) && Primitive::from_glyph(c).is_none()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_escapes() {
+        let lex_one = |input: &str| {
+            let (tokens, errors) = lex(input, (), &mut Inputs::default());
+            (tokens.into_iter().next().map(|t| t.value), errors)
+        };
+        let (token, errors) = lex_one(r#""\x41\0\u{1F600}""#);
+        assert!(errors.is_empty());
+        assert_eq!(token, Some(Token::Str("A\0😀".into())));
+        let (token, errors) = lex_one(r"@\0");
+        assert!(errors.is_empty());
+        assert_eq!(token, Some(Token::Char("\0".into())));
+        let (_, errors) = lex_one(r#""\u{D800}""#);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].value.to_string(),
+            "Invalid escaped code point U+D800"
+        );
+    }
+
+    #[test]
+    fn tab_width_columns() {
+        for (tab_width, col) in [(1, 2), (4, 5)] {
+            let mut inputs = Inputs::default();
+            let (tokens, _) = lex_with_tab_width("\tfoo", (), &mut inputs, tab_width);
+            let foo = tokens.iter().find(|t| t.value == Token::Ident).unwrap();
+            assert_eq!(foo.span.start.col, col);
+            assert_eq!(foo.span.start.byte_pos, 1);
+        }
+        let (tokens, _) = lex_with_tab_width("\t\tfoo", (), &mut Inputs::default(), u16::MAX);
+        let foo = tokens.iter().find(|t| t.value == Token::Ident).unwrap();
+        assert_eq!(foo.span.start.col, u16::MAX);
+    }
+}
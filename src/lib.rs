@@ -101,10 +101,13 @@ The `uiua` crate has the following noteable feature flags:
 - `bytes`: Enables a byte array type. This type is semantically equivalent to a numeric array, but takes up less space. It is returned by certain file and network functions, as well as some comparison functions.
 - `native_sys`: Enables the [`NativeSys`] backend. This is the default backend used by the interpreter.
 - `audio`: Enables audio features in the [`NativeSys`] backend.
+- `audio_formats`: Enables MP3 and OGG/Vorbis playback in the [`NativeSys`] backend.
+- `clipboard`: Enables clipboard access in the [`NativeSys`] backend.
 - `https`: Enables the `&httpsw` system function
+- `gzip`: Enables transparent decompression of gzip and deflate encoded HTTPS responses
 - `invoke`: Enables the `&invk` system function
 - `trash`: Enables the `&ftr` system function
-- `raw_mode`: Enables the `&raw` system function
+- `raw_mode`: Enables the `&raw` system function, reading single key presses, and terminal cursor control
 */
 
 #![allow(clippy::single_match, clippy::needless_range_loop)]
@@ -136,6 +139,8 @@ mod shape;
 #[doc(hidden)]
 pub mod stand;
 mod sys;
+mod sys_capture;
+mod sys_mem;
 #[cfg(feature = "native_sys")]
 mod sys_native;
 mod value;
@@ -151,11 +156,13 @@ pub use self::{
     lex::*,
     lsp::*,
     lsp::{spans, SpanKind},
-    parse::{parse, ParseError},
+    parse::{parse, parse_stream, parse_tokens, ItemStream, ParseError},
     primitive::*,
     run::*,
     shape::*,
     sys::*,
+    sys_capture::*,
+    sys_mem::*,
     value::*,
 };
 
@@ -1,4 +1,8 @@
-use std::{error::Error, fmt, mem::replace};
+use std::{
+    error::Error,
+    fmt,
+    mem::{replace, take},
+};
 
 use ecow::EcoString;
 
@@ -103,6 +107,42 @@ impl fmt::Display for ParseError {
     }
 }
 
+impl Sp<ParseError> {
+    /// Check if the error came from the lexer
+    pub fn is_lex(&self) -> bool {
+        matches!(self.value, ParseError::Lex(_))
+    }
+    /// Get the start and end byte offsets of the error
+    pub fn byte_span(&self) -> (usize, usize) {
+        let range = self.span.byte_range();
+        (range.start, range.end)
+    }
+    /// Get a stable, kebab-case code categorizing the error
+    ///
+    /// This is intended for tooling and will not change even if the error message does.
+    pub fn code(&self) -> &'static str {
+        match &self.value {
+            ParseError::Lex(e) => match e {
+                LexError::UnexpectedChar(_) => "unexpected-char",
+                LexError::ExpectedCharacter(chars) if chars == &['"'] => "unterminated-string",
+                LexError::ExpectedCharacter(_) => "expected-char",
+                LexError::InvalidEscape(_) | LexError::InvalidCodePoint(_) => "invalid-escape",
+                LexError::ExpectedNumber => "expected-number",
+            },
+            ParseError::Expected(..) => "expected-token",
+            ParseError::InvalidNumber(_) => "invalid-number",
+            ParseError::Unexpected(_) => "unexpected-token",
+            ParseError::InvalidArgCount(_) => "invalid-arg-count",
+            ParseError::InvalidOutCount(_) => "invalid-out-count",
+            ParseError::AmpersandBindingName => "ampersand-binding-name",
+            ParseError::FunctionNotAllowed => "function-not-allowed",
+            ParseError::SplitInModifier => "split-in-modifier",
+            ParseError::UnsplitInModifier => "unsplit-in-modifier",
+            ParseError::LineTooLong(_) => "line-too-long",
+        }
+    }
+}
+
 const STYLE_MAX_WIDTH: usize = 40;
 const ADVICE_MAX_WIDTH: usize = 53;
 const WARNING_MAX_WIDTH: usize = 67;
@@ -117,12 +157,129 @@ pub fn parse(
     inputs: &mut Inputs,
 ) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
     let (tokens, lex_errors) = lex(input, src, inputs);
-    fn parse(
+    parse_impl(input, inputs, tokens, lex_errors)
+}
+
+/// Parse already-lexed tokens into an AST
+///
+/// The tokens must have been lexed with the same [`Inputs`],
+/// since their source is looked up to produce error messages.
+pub fn parse_tokens(
+    tokens: Vec<Sp<crate::lex::Token>>,
+    inputs: &mut Inputs,
+) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
+    let input = match tokens.first() {
+        Some(token) => inputs.get(&token.span.src),
+        None => EcoString::new(),
+    };
+    parse_impl(&input, inputs, tokens, Vec::new())
+}
+
+fn parse_impl(
+    input: &str,
+    inputs: &mut Inputs,
+    tokens: Vec<Sp<crate::lex::Token>>,
+    lex_errors: Vec<Sp<LexError>>,
+) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
+    let mut parser = Parser::new(input, inputs, tokens, lex_errors);
+    let items = parser.items(true);
+    parser.check_eof();
+    (items, parser.errors, parser.diagnostics)
+}
+
+/// Parse Uiua code into an AST one item at a time
+///
+/// Each item is returned alongside the errors that occurred in it,
+/// including lexing errors in its code.
+/// Errors after the last item are returned without an item.
+pub fn parse_stream<'i>(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &'i mut Inputs,
+) -> ItemStream<'i> {
+    let (tokens, lex_errors) = lex(input, src, inputs);
+    let mut parser = Parser::new(input, inputs, tokens, lex_errors);
+    let mut early_errors = take(&mut parser.errors);
+    early_errors.sort_by_key(|error| error.span.start.byte_pos);
+    parser.skip_newlines();
+    ItemStream {
+        parser,
+        early_errors,
+        had_errors: false,
+        done: false,
+    }
+}
+
+/// An iterator over the items of some Uiua code
+///
+/// Created by [`parse_stream`]
+pub struct ItemStream<'i> {
+    parser: Parser<'i>,
+    /// Errors found before parsing, in order of location
+    early_errors: Vec<Sp<ParseError>>,
+    /// Whether any errors have been yielded
+    had_errors: bool,
+    done: bool,
+}
+
+impl<'i> ItemStream<'i> {
+    /// Get the diagnostics produced so far
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.parser.diagnostics
+    }
+}
+
+impl<'i> Iterator for ItemStream<'i> {
+    type Item = (Option<Item>, Vec<Sp<ParseError>>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.parser.next_item(true);
+        if item.is_none() {
+            self.done = true;
+            // Like `parse`, only report trailing tokens if there were no other errors
+            if !self.had_errors && self.early_errors.is_empty() {
+                self.parser.check_eof();
+            }
+        }
+        // Early errors before the next token belong to this item
+        let end = match self.parser.tokens.get(self.parser.index) {
+            Some(token) if !self.done => token.span.start.byte_pos,
+            _ => u32::MAX,
+        };
+        let early = (self.early_errors.iter())
+            .take_while(|error| error.span.start.byte_pos < end)
+            .count();
+        let mut errors: Vec<_> = self.early_errors.drain(..early).collect();
+        errors.append(&mut self.parser.errors);
+        self.had_errors |= !errors.is_empty();
+        if item.is_none() && errors.is_empty() {
+            return None;
+        }
+        Some((item, errors))
+    }
+}
+
+struct Parser<'i> {
+    inputs: &'i mut Inputs,
+    input: EcoString,
+    tokens: Vec<Sp<crate::lex::Token>>,
+    index: usize,
+    next_output_comment: usize,
+    errors: Vec<Sp<ParseError>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+type FunctionContents = (Option<Sp<Signature>>, Vec<Vec<Sp<Word>>>, Option<CodeSpan>);
+
+impl<'i> Parser<'i> {
+    fn new(
         input: &str,
-        inputs: &mut Inputs,
+        inputs: &'i mut Inputs,
         tokens: Vec<Sp<crate::lex::Token>>,
         lex_errors: Vec<Sp<LexError>>,
-    ) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
+    ) -> Self {
         let mut errors: Vec<_> = lex_errors
             .into_iter()
             .map(|e| e.map(ParseError::Lex))
@@ -183,8 +340,7 @@ pub fn parse(
             ));
         }
 
-        // Parse
-        let mut parser = Parser {
+        Parser {
             inputs,
             input: input.into(),
             tokens,
@@ -192,34 +348,15 @@ pub fn parse(
             errors,
             diagnostics,
             next_output_comment: 0,
-        };
-        let items = parser.items(true);
-        if parser.errors.is_empty() && parser.index < parser.tokens.len() {
-            parser.errors.push(
-                parser
-                    .tokens
-                    .remove(parser.index)
-                    .map(ParseError::Unexpected),
-            );
         }
-        (items, parser.errors, parser.diagnostics)
     }
-    parse(input, inputs, tokens, lex_errors)
-}
-
-struct Parser<'i> {
-    inputs: &'i mut Inputs,
-    input: EcoString,
-    tokens: Vec<Sp<crate::lex::Token>>,
-    index: usize,
-    next_output_comment: usize,
-    errors: Vec<Sp<ParseError>>,
-    diagnostics: Vec<Diagnostic>,
-}
-
-type FunctionContents = (Option<Sp<Signature>>, Vec<Vec<Sp<Word>>>, Option<CodeSpan>);
-
-impl<'i> Parser<'i> {
+    /// Report leftover tokens if there were no other errors
+    fn check_eof(&mut self) {
+        if self.errors.is_empty() && self.index < self.tokens.len() {
+            let token = self.tokens.remove(self.index);
+            self.errors.push(token.map(ParseError::Unexpected));
+        }
+    }
     fn next_token_map<'a, T: 'a>(
         &'a mut self,
         f: impl FnOnce(&'a Token) -> Option<T>,
@@ -268,29 +405,35 @@ impl<'i> Parser<'i> {
     }
     fn items(&mut self, parse_scopes: bool) -> Vec<Item> {
         let mut items = Vec::new();
+        self.skip_newlines();
+        while let Some(item) = self.next_item(parse_scopes) {
+            items.push(item);
+        }
+        items
+    }
+    fn skip_newlines(&mut self) {
         while self.try_exact(Newline).is_some() {
             self.try_spaces();
         }
+    }
+    fn next_item(&mut self, parse_scopes: bool) -> Option<Item> {
         loop {
-            match self.try_item(parse_scopes) {
-                Some(item) => items.push(item),
-                None => {
-                    if self.try_exact(Newline).is_none() {
-                        break;
-                    }
-                    self.try_spaces();
-                    let mut extra_newlines = false;
-                    while self.try_exact(Newline).is_some() {
-                        extra_newlines = true;
-                        self.try_spaces();
-                    }
-                    if extra_newlines {
-                        items.push(Item::Words(vec![Vec::new()]));
-                    }
-                }
+            if let Some(item) = self.try_item(parse_scopes) {
+                return Some(item);
+            }
+            if self.try_exact(Newline).is_none() {
+                return None;
+            }
+            self.try_spaces();
+            let mut extra_newlines = false;
+            while self.try_exact(Newline).is_some() {
+                extra_newlines = true;
+                self.try_spaces();
+            }
+            if extra_newlines {
+                return Some(Item::Words(vec![Vec::new()]));
             }
         }
-        items
     }
     fn try_item(&mut self, parse_scopes: bool) -> Option<Item> {
         self.try_spaces();
@@ -753,7 +896,7 @@ impl<'i> Parser<'i> {
         } else if let Some(start) = self.try_exact(OpenBracket) {
             while self.try_exact(Newline).is_some() {}
             let items = self.multiline_words();
-            let end = self.expect_close(CloseBracket);
+            let end = self.expect_close(CloseBracket, &start);
             let span = start.merge(end.span);
             let arr = Arr {
                 lines: items,
@@ -778,7 +921,7 @@ impl<'i> Parser<'i> {
         } else if let Some(start) = self.try_exact(OpenCurly) {
             while self.try_exact(Newline).is_some() {}
             let items = self.multiline_words();
-            let end = self.expect_close(CloseCurly);
+            let end = self.expect_close(CloseCurly, &start);
             let span = start.merge(end.span);
             span.sp(Word::Array(Arr {
                 lines: items,
@@ -848,7 +991,7 @@ impl<'i> Parser<'i> {
                     closed: true,
                 }))
             }
-            let end = self.expect_close(CloseParen);
+            let end = self.expect_close(CloseParen, &start);
             let (first_sig, first_lines, first_span) = first;
             let outer_span = start.clone().merge(end.span);
             if branches.is_empty() {
@@ -922,14 +1065,32 @@ impl<'i> Parser<'i> {
     fn try_spaces(&mut self) -> Option<Sp<Word>> {
         self.try_exact(Spaces).map(|span| span.sp(Word::Spaces))
     }
-    fn expect_close(&mut self, ascii: AsciiToken) -> Sp<bool> {
+    /// Expect a closing delimiter
+    ///
+    /// If it is missing, the error spans from the `open` delimiter to the current token.
+    /// If a binding comes next, the delimited code is ended before the line break
+    /// so that the binding and the rest of the code still parse normally.
+    fn expect_close(&mut self, ascii: AsciiToken, open: &CodeSpan) -> Sp<bool> {
         if let Some(span) = self.try_exact(ascii) {
-            span.sp(true)
-        } else {
-            self.errors
-                .push(self.expected([Expectation::Term, Expectation::Simple(ascii)]));
-            self.prev_span().sp(false)
+            return span.sp(true);
         }
+        let curr = self.index;
+        let before_binding = self.try_binding_init().is_some();
+        self.index = curr;
+        if before_binding {
+            while (self.index.checked_sub(1))
+                .is_some_and(|i| matches!(self.tokens[i].value, Newline | Spaces))
+            {
+                self.index -= 1;
+            }
+        }
+        let mut err = self.expected([Expectation::Term, Expectation::Simple(ascii)]);
+        if let (true, ParseError::Expected(_, found)) = (before_binding, &mut err.value) {
+            *found = None;
+        }
+        err.span = open.clone().merge(err.span);
+        self.errors.push(err);
+        self.prev_span().sp(false)
     }
     fn validate_words(&mut self, words: &[Sp<Word>], allow_func: bool) {
         for word in words {
@@ -1181,3 +1342,119 @@ fn arr_is_normal_di(arr: &Arr) -> bool {
     });
     is_di
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(input: &str) -> Vec<Sp<ParseError>> {
+        parse(input, (), &mut Inputs::default()).1
+    }
+
+    #[test]
+    fn error_codes() {
+        let errs = errors("+ 1 \"abc");
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].is_lex());
+        assert_eq!(errs[0].code(), "unterminated-string");
+        assert_eq!(errs[0].byte_span(), (4, 8));
+
+        let errs = errors("+ 1)");
+        assert!(!errs[0].is_lex());
+        assert_eq!(errs[0].code(), "unexpected-token");
+        assert_eq!(errs[0].byte_span(), (3, 4));
+
+        assert!(errors("+ 1 2").is_empty());
+    }
+
+    #[test]
+    fn unclosed_delimiter_span() {
+        for (input, start) in [("+ (× 2 3", 2), ("[1 2 3", 0), ("⊂ {1 2", 4)] {
+            let errs = errors(input);
+            let err = errs
+                .iter()
+                .find(|err| err.code() == "expected-token")
+                .unwrap();
+            assert_eq!(err.byte_span(), (start, input.len()), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn unclosed_before_binding() {
+        let input = "{1 2\nX ← 5\n+ X 1";
+        let (items, errs, _) = parse(input, (), &mut Inputs::default());
+        assert_eq!(errs.len(), 1, "{errs:?}");
+        assert_eq!(errs[0].code(), "expected-token");
+        assert_eq!(errs[0].byte_span(), (0, "{1 2".len()));
+        assert_eq!(errs[0].value.to_string(), "Expected term or `}`");
+        assert!(matches!(&items[1], Item::Binding(binding) if &*binding.name.value == "X"));
+        assert_eq!(items.len(), 3);
+    }
+
+    #[test]
+    fn parse_lexed_tokens() {
+        let code = "Square ← ×.\n+ 1 Square 2 # comment\n[1 2 3]";
+        let mut inputs = Inputs::default();
+        let (tokens, lex_errors) = lex(code, (), &mut inputs);
+        assert!(lex_errors.is_empty());
+        let (from_tokens, errors, _) = parse_tokens(tokens, &mut inputs);
+        assert!(errors.is_empty());
+        let (from_source, errors, _) = parse(code, (), &mut Inputs::default());
+        assert!(errors.is_empty());
+        assert_eq!(format!("{from_tokens:?}"), format!("{from_source:?}"));
+    }
+
+    #[test]
+    fn stream_items() {
+        let mut inputs = Inputs::default();
+        let mut stream = parse_stream("A ← 1\nB ← 2\nC ← 3", (), &mut inputs);
+        let mut next_name = || match stream.next() {
+            Some((Some(Item::Binding(binding)), errors)) if errors.is_empty() => {
+                binding.name.value.to_string()
+            }
+            item => panic!("Expected a binding, found {item:?}"),
+        };
+        assert_eq!(next_name(), "A");
+        assert_eq!(next_name(), "B");
+        assert_eq!(next_name(), "C");
+        assert!(stream.next().is_none());
+
+        let mut inputs = Inputs::default();
+        let mut stream = parse_stream("+ 1 2\n+ 1)", (), &mut inputs);
+        assert!(matches!(stream.next(), Some((Some(Item::Words(_)), e)) if e.is_empty()));
+        let (_, errors) = stream.next().unwrap();
+        assert_eq!(errors[0].code(), "unexpected-token");
+    }
+
+    #[test]
+    fn stream_errors_match_parse() {
+        for input in ["A ← 1\nB ← 2\n)", "A ← \"\\q\"\nB ← 2\n)", "+ 1)\nB ← 2\n)"] {
+            let codes = |errors: Vec<Sp<ParseError>>| {
+                (errors.into_iter())
+                    .map(|e| (e.code(), e.byte_span()))
+                    .collect::<Vec<_>>()
+            };
+            let (_, errors, _) = parse(input, (), &mut Inputs::default());
+            let mut inputs = Inputs::default();
+            let stream = parse_stream(input, (), &mut inputs);
+            let stream_errors = stream.flat_map(|(_, errors)| errors).collect();
+            assert_eq!(codes(stream_errors), codes(errors), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn stream_late_lex_error() {
+        let mut inputs = Inputs::default();
+        let mut stream = parse_stream("A ← 1\nB ← 2\nC ← \"\\q\"", (), &mut inputs);
+        for name in ["A", "B"] {
+            let (item, errors) = stream.next().unwrap();
+            assert!(matches!(item, Some(Item::Binding(b)) if &*b.name.value == name));
+            assert!(errors.is_empty(), "{errors:?}");
+        }
+        let (item, errors) = stream.next().unwrap();
+        assert!(matches!(item, Some(Item::Binding(b)) if &*b.name.value == "C"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "invalid-escape");
+        assert!(stream.next().is_none());
+    }
+}
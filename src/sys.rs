@@ -484,6 +484,79 @@ impl From<Handle> for Value {
     }
 }
 
+/// A protocol for drawing images in a terminal
+///
+/// Not every terminal supports every protocol.
+/// If the requested protocol is not supported, the image falls back to [`ImageProtocol::HalfBlock`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ImageProtocol {
+    /// Detect the best protocol from the environment
+    #[default]
+    Auto,
+    /// Colored half-block characters, which work in any terminal with color support
+    HalfBlock,
+    /// The DEC Sixel graphics protocol
+    Sixel,
+    /// The Kitty graphics protocol
+    Kitty,
+    /// The iTerm2 inline image protocol
+    Iterm,
+}
+
+/// The kind of stream a [`Handle`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum HandleKind {
+    File,
+    TcpListener,
+    TcpSocket,
+}
+
+/// The number of bytes that have gone through a stream handle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandleStats {
+    /// The number of bytes read
+    pub read: u64,
+    /// The number of bytes written
+    pub written: u64,
+}
+
+/// A key press read from the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// The key that was pressed
+    pub code: KeyCode,
+    /// Whether the control key was held
+    pub ctrl: bool,
+    /// Whether the alt key was held
+    pub alt: bool,
+    /// Whether the shift key was held
+    pub shift: bool,
+}
+
+/// The code of a [`Key`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum KeyCode {
+    Char(char),
+    /// A function key, such as `F1`
+    F(u8),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Esc,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
 /// The function type passed to `&ast`
 pub type AudioStreamFn = Box<dyn FnMut(&[f64]) -> UiuaResult<Vec<[f64; 2]>> + Send>;
 
@@ -515,10 +588,23 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn scan_line_stdin(&self) -> Result<Option<String>, String> {
         Err("Reading from stdin is not supported in this environment".into())
     }
+    /// Check whether stdin can be read from without blocking
+    ///
+    /// Should return `Ok(true)` if EOF is reached, so that a subsequent read can observe it.
+    fn stdin_ready(&self) -> Result<bool, String> {
+        Err("Checking stdin is not supported in this environment".into())
+    }
     /// Set the terminal to raw mode
     fn set_raw_mode(&self, raw_mode: bool) -> Result<(), String> {
         Err("Setting raw mode is not supported in this environment".into())
     }
+    /// Read a single key press from the terminal
+    ///
+    /// Raw mode should be enabled with [`SysBackend::set_raw_mode`] first.
+    /// Should return `Ok(None)` if EOF is reached.
+    fn read_key(&self) -> Result<Option<Key>, String> {
+        Err("Reading keys is not supported in this environment".into())
+    }
     /// Get an environment variable
     fn var(&self, name: &str) -> Option<String> {
         None
@@ -527,6 +613,48 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn term_size(&self) -> Result<(usize, usize), String> {
         Err("Getting the terminal size is not supported in this environment".into())
     }
+    /// Move the terminal cursor to a 0-indexed row and column
+    fn term_move_cursor(&self, row: usize, col: usize) -> Result<(), String> {
+        Err("Moving the cursor is not supported in this environment".into())
+    }
+    /// Clear the terminal screen
+    fn term_clear(&self) -> Result<(), String> {
+        Err("Clearing the terminal is not supported in this environment".into())
+    }
+    /// Save the terminal cursor position
+    fn term_save_cursor(&self) -> Result<(), String> {
+        Err("Saving the cursor is not supported in this environment".into())
+    }
+    /// Restore the terminal cursor position saved by [`SysBackend::term_save_cursor`]
+    fn term_restore_cursor(&self) -> Result<(), String> {
+        Err("Restoring the cursor is not supported in this environment".into())
+    }
+    /// Enter or leave the terminal's alternate screen
+    ///
+    /// Leaving the alternate screen restores whatever was on the terminal before it was entered.
+    /// ```
+    /// use uiua::*;
+    ///
+    /// fn show_title(sys: &impl SysBackend, title: &str) -> Result<(), String> {
+    ///     sys.term_set_alternate_screen(true)?;
+    ///     sys.term_clear()?;
+    ///     sys.term_move_cursor(0, 0)?;
+    ///     sys.print_str_stdout(title)?;
+    ///     sys.read_key()?;
+    ///     sys.term_set_alternate_screen(false)
+    /// }
+    /// ```
+    fn term_set_alternate_screen(&self, on: bool) -> Result<(), String> {
+        Err("The alternate screen is not supported in this environment".into())
+    }
+    /// Get the text contents of the clipboard
+    fn clipboard_get(&self) -> Result<String, String> {
+        Err("Getting the clipboard is not supported in this environment".into())
+    }
+    /// Set the text contents of the clipboard
+    fn clipboard_set(&self, text: &str) -> Result<(), String> {
+        Err("Setting the clipboard is not supported in this environment".into())
+    }
     /// Check if a file exists
     fn file_exists(&self, path: &str) -> bool {
         false
@@ -539,6 +667,22 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn is_file(&self, path: &str) -> Result<bool, String> {
         Err("This IO operation is not supported in this environment".into())
     }
+    /// Get the absolute path of a file or directory, with all symlinks resolved
+    ///
+    /// The path must exist. Use [`normalize_path`] for paths that may not.
+    fn canonicalize(&self, path: &str) -> Result<String, String> {
+        Err("This IO operation is not supported in this environment".into())
+    }
+    /// Create a symbolic link at `link` that points to `target`
+    ///
+    /// On Windows, creating symlinks requires either administrator privileges or Developer Mode.
+    fn symlink(&self, target: &str, link: &str) -> Result<(), String> {
+        Err("This IO operation is not supported in this environment".into())
+    }
+    /// Get the target of a symbolic link
+    fn read_link(&self, path: &str) -> Result<String, String> {
+        Err("This IO operation is not supported in this environment".into())
+    }
     /// Delete a file or directory
     fn delete(&self, path: &str) -> Result<(), String> {
         Err("This IO operation is not supported in this environment".into())
@@ -551,6 +695,20 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
         Err("This IO operation is not supported in this environment".into())
     }
+    /// Read exactly `count` bytes from a stream
+    ///
+    /// Returns an error if the stream ends first.
+    fn read_exact(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
+        let mut buffer = Vec::with_capacity(count);
+        while buffer.len() < count {
+            let bytes = self.read(handle, count - buffer.len())?;
+            if bytes.is_empty() {
+                return Err("Unexpected EOF".into());
+            }
+            buffer.extend_from_slice(&bytes);
+        }
+        Ok(buffer)
+    }
     /// Read from a stream until a delimiter is reached
     fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String> {
         let mut buffer = Vec::new();
@@ -570,10 +728,23 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String> {
         Err("This IO operation is not supported in this environment".into())
     }
+    /// Write as many bytes as a stream will accept without blocking
+    ///
+    /// Returns the number of bytes written, which is `0` if the stream is not ready.
+    fn write_some(&self, handle: Handle, contents: &[u8]) -> Result<usize, String> {
+        Err("This IO operation is not supported in this environment".into())
+    }
     /// Create a file
     fn create_file(&self, path: &Path) -> Result<Handle, String> {
         Err("This IO operation is not supported in this environment".into())
     }
+    /// Create a uniquely named file in the system's temporary directory
+    ///
+    /// Returns the path of the file and a writable handle to it.
+    /// The caller is responsible for deleting the file when it is no longer needed.
+    fn temp_file(&self) -> Result<(String, Handle), String> {
+        Err("This IO operation is not supported in this environment".into())
+    }
     /// Open a file
     fn open_file(&self, path: &Path) -> Result<Handle, String> {
         Err("This IO operation is not supported in this environment".into())
@@ -585,6 +756,51 @@ pub trait SysBackend: Any + Send + Sync + 'static {
         self.close(handle)?;
         Ok(bytes)
     }
+    /// Read a file in chunks of `chunk_size` bytes
+    ///
+    /// The last chunk may be shorter.
+    /// Reading stops early if `f` returns an error.
+    fn read_chunks(
+        &self,
+        path: &Path,
+        chunk_size: usize,
+        f: &mut dyn FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if chunk_size == 0 {
+            return Err("Chunk size must be positive".into());
+        }
+        let handle = self.open_file(path)?;
+        let mut read_all = || loop {
+            let chunk = self.read(handle, chunk_size)?;
+            if chunk.is_empty() {
+                return Ok(());
+            }
+            f(&chunk)?;
+        };
+        let res = read_all();
+        self.close(handle)?;
+        res
+    }
+    /// Guess whether a file is binary rather than text
+    ///
+    /// Only the first 8KB of the file are checked.
+    /// A file is considered binary if it contains a NUL byte
+    /// or if more than 10% of its bytes are control characters other than whitespace or escape.
+    /// Empty files are considered text.
+    fn is_binary_file(&self, path: &str) -> Result<bool, String> {
+        let handle = self.open_file(path.as_ref())?;
+        let bytes = self.read(handle, 8192);
+        self.close(handle)?;
+        let bytes = bytes?;
+        if bytes.contains(&0) {
+            return Ok(true);
+        }
+        let control = bytes
+            .iter()
+            .filter(|&&b| (b < 0x20 && !b"\t\n\r\x0c\x1b".contains(&b)) || b == 0x7f)
+            .count();
+        Ok(control * 10 > bytes.len())
+    }
     /// Write all bytes to a file
     fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
         let handle = self.create_file(path)?;
@@ -592,6 +808,13 @@ pub trait SysBackend: Any + Send + Sync + 'static {
         self.close(handle)?;
         Ok(())
     }
+    /// Write all bytes to a file atomically
+    ///
+    /// Readers of the file should see either the old contents or the new contents, never a mix.
+    /// If the file already exists, its permissions are kept.
+    fn write_atomic(&self, path: &str, contents: &[u8]) -> Result<(), String> {
+        Err("Atomic writes are not supported in this environment".into())
+    }
     /// Sleep the current thread for `seconds` seconds
     fn sleep(&self, seconds: f64) -> Result<(), String> {
         Err("Sleeping is not supported in this environment".into())
@@ -601,6 +824,24 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn show_image(&self, image: DynamicImage) -> Result<(), String> {
         Err("Showing images not supported in this environment".into())
     }
+    /// Show an image using a specific terminal image protocol
+    ///
+    /// Backends that do not draw to a terminal ignore the protocol.
+    #[cfg(feature = "image")]
+    fn show_image_protocol(
+        &self,
+        image: DynamicImage,
+        protocol: ImageProtocol,
+    ) -> Result<(), String> {
+        self.show_image(image)
+    }
+    /// Save an image to a file
+    ///
+    /// The format is inferred from the path's extension.
+    #[cfg(feature = "image")]
+    fn save_image(&self, image: DynamicImage, path: &str) -> Result<(), String> {
+        Err("Saving images not supported in this environment".into())
+    }
     /// Show a GIF
     fn show_gif(&self, gif_bytes: Vec<u8>) -> Result<(), String> {
         Err("Showing gifs not supported in this environment".into())
@@ -617,6 +858,13 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn stream_audio(&self, f: AudioStreamFn) -> Result<(), String> {
         Err("Streaming audio not supported in this environment".into())
     }
+    /// Record audio from the default input device for `seconds` seconds
+    ///
+    /// Returns WAV bytes that can be passed to [`SysBackend::play_audio`].
+    /// Returns an error if `seconds` is negative or not finite.
+    fn record_audio(&self, seconds: f64) -> Result<Vec<u8>, String> {
+        Err("Recording audio not supported in this environment".into())
+    }
     /// Create a TCP listener and bind it to an address
     fn tcp_listen(&self, addr: &str) -> Result<Handle, String> {
         Err("TCP listeners are not supported in this environment".into())
@@ -629,10 +877,29 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn tcp_connect(&self, addr: &str) -> Result<Handle, String> {
         Err("TCP sockets are not supported in this environment".into())
     }
+    /// Connect to a TCP socket, trying all of the host's addresses
+    ///
+    /// Connection attempts are started a short time apart, alternating between
+    /// IPv6 and IPv4, and the first one to succeed is used.
+    /// An error is returned if no attempt succeeds within `timeout`.
+    fn tcp_connect_fast(&self, addr: &str, timeout: Duration) -> Result<Handle, String> {
+        Err("TCP sockets are not supported in this environment".into())
+    }
+    /// Create a TCP socket bound to a local address and connect it to a remote address
+    ///
+    /// The local and remote addresses must be of the same family.
+    /// The connection is never made through a proxy.
+    fn tcp_connect_from(&self, local: &str, remote: &str) -> Result<Handle, String> {
+        Err("TCP sockets are not supported in this environment".into())
+    }
     /// Get the connection address of a TCP socket
     fn tcp_addr(&self, handle: Handle) -> Result<String, String> {
         Err("TCP sockets are not supported in this environment".into())
     }
+    /// Get the local address of a TCP socket
+    fn tcp_local_addr(&self, handle: Handle) -> Result<String, String> {
+        Err("TCP sockets are not supported in this environment".into())
+    }
     /// Set a TCP socket to non-blocking mode
     fn tcp_set_non_blocking(&self, handle: Handle, non_blocking: bool) -> Result<(), String> {
         Err("TCP sockets are not supported in this environment".into())
@@ -653,6 +920,30 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     ) -> Result<(), String> {
         Err("TCP sockets are not supported in this environment".into())
     }
+    /// Get the kind of stream a handle refers to
+    ///
+    /// Should return `None` if the handle is not open.
+    fn handle_kind(&self, handle: Handle) -> Option<HandleKind> {
+        None
+    }
+    /// Get the number of bytes read from and written to a stream
+    ///
+    /// Should return `None` if the handle is not open.
+    fn handle_stats(&self, handle: Handle) -> Option<HandleStats> {
+        None
+    }
+    /// Get all open stream handles and their kinds
+    ///
+    /// This is a snapshot, so handles may be opened or closed by other threads by the time it is used.
+    fn live_handles(&self) -> Vec<(Handle, HandleKind)> {
+        Vec::new()
+    }
+    /// Flush all buffered writes to open streams
+    ///
+    /// This is useful to call before exiting, since unclosed streams may not have written everything.
+    fn flush_all(&self) -> Result<(), String> {
+        Ok(())
+    }
     /// Close a stream
     fn close(&self, handle: Handle) -> Result<(), String> {
         Ok(())
@@ -673,14 +964,44 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     ) -> Result<(i32, String, String), String> {
         Err("Running commands is not supported in this environment".into())
     }
+    /// Run a command, capturing standard IO
+    ///
+    /// The command is killed and an error is returned if it does not finish within `timeout`.
+    fn run_command_capture_timeout(
+        &self,
+        command: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<(i32, String, String), String> {
+        Err("Running commands is not supported in this environment".into())
+    }
+    /// Check if a command is available to run
+    ///
+    /// Commands without a path separator should be searched for in the `PATH`.
+    fn command_exists(&self, command: &str) -> bool {
+        false
+    }
     /// Change the current directory
     fn change_directory(&self, path: &str) -> Result<(), String> {
         Err("Changing directories is not supported in this environment".into())
     }
     /// Make an HTTPS request on a TCP socket
+    ///
+    /// The response must be valid UTF-8.
+    /// Use [`SysBackend::https_get_bytes`] to get the raw response.
     fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
+        let bytes = self.https_get_bytes(request, handle)?;
+        String::from_utf8(bytes)
+            .map_err(|e| format!("Error converting HTTP Response to utf-8: {e}"))
+    }
+    /// Make an HTTPS request on a TCP socket, returning the raw response
+    fn https_get_bytes(&self, request: &str, handle: Handle) -> Result<Vec<u8>, String> {
         Err("Making HTTPS requests is not supported in this environment".into())
     }
+    /// Trust the PEM-encoded root certificates for future HTTPS requests
+    fn https_add_root_cert(&self, pem: &[u8]) -> Result<(), String> {
+        Err("Adding root certificates is not supported in this environment".into())
+    }
 }
 
 impl fmt::Debug for dyn SysBackend {
@@ -1818,3 +2139,57 @@ pub fn gif_bytes_to_value(bytes: &[u8]) -> Result<(f64, Value), gif::DecodingErr
     num.compress();
     Ok((frame_rate, num))
 }
+
+/// Lexically resolve `.` and `..` components in a path without touching the filesystem
+///
+/// Unlike [`SysBackend::canonicalize`], this does not resolve symlinks,
+/// so `a/..` is always removed even if `a` is a link to somewhere else.
+pub fn normalize_path(path: &str) -> String {
+    use std::path::{Component, PathBuf};
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // `..` at the root is the root
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                Some(Component::ParentDir) | None => normalized.push(".."),
+                Some(Component::CurDir) => unreachable!(),
+            },
+            component => normalized.push(component),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        ".".into()
+    } else {
+        normalized.to_string_lossy().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_https_response() {
+        struct BinaryHttps;
+        impl SysBackend for BinaryHttps {
+            fn any(&self) -> &dyn Any {
+                self
+            }
+            fn any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+            fn https_get_bytes(&self, _: &str, _: Handle) -> Result<Vec<u8>, String> {
+                Ok(b"HTTP/1.0 200 OK\r\n\r\n\xFF".to_vec())
+            }
+        }
+        let bytes = BinaryHttps.https_get_bytes("GET /", Handle(0)).unwrap();
+        assert_eq!(bytes.last(), Some(&0xFF));
+        let err = BinaryHttps.https_get("GET /", Handle(0)).unwrap_err();
+        assert!(err.contains("utf-8"));
+    }
+}
@@ -0,0 +1,315 @@
+use std::{any::Any, path::Path, time::Duration};
+
+use parking_lot::Mutex;
+
+#[cfg(feature = "image")]
+use crate::ImageProtocol;
+use crate::{AudioStreamFn, Handle, HandleKind, HandleStats, Key, SysBackend};
+
+/// A system backend wrapper that captures stdout and stderr
+///
+/// Printed text is stored in buffers instead of being printed.
+/// Everything else is forwarded to the inner backend.
+pub struct CapturingSys<B> {
+    inner: B,
+    stdout: Mutex<String>,
+    stderr: Mutex<String>,
+}
+
+impl<B> CapturingSys<B> {
+    /// Wrap a backend
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            stdout: Mutex::new(String::new()),
+            stderr: Mutex::new(String::new()),
+        }
+    }
+    /// Take everything printed to stdout so far
+    pub fn take_stdout(&self) -> String {
+        std::mem::take(&mut self.stdout.lock())
+    }
+    /// Take everything printed to stderr so far
+    pub fn take_stderr(&self) -> String {
+        std::mem::take(&mut self.stderr.lock())
+    }
+    /// Get a reference to the inner backend
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+    /// Unwrap the inner backend
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: SysBackend> SysBackend for CapturingSys<B> {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn print_str_stdout(&self, s: &str) -> Result<(), String> {
+        self.stdout.lock().push_str(s);
+        Ok(())
+    }
+    fn print_str_stderr(&self, s: &str) -> Result<(), String> {
+        self.stderr.lock().push_str(s);
+        Ok(())
+    }
+    fn save_error_color(&self, message: String, colored: String) {
+        self.inner.save_error_color(message, colored)
+    }
+    fn print_str_trace(&self, s: &str) {
+        self.inner.print_str_trace(s)
+    }
+    fn scan_line_stdin(&self) -> Result<Option<String>, String> {
+        self.inner.scan_line_stdin()
+    }
+    fn stdin_ready(&self) -> Result<bool, String> {
+        self.inner.stdin_ready()
+    }
+    fn set_raw_mode(&self, raw_mode: bool) -> Result<(), String> {
+        self.inner.set_raw_mode(raw_mode)
+    }
+    fn read_key(&self) -> Result<Option<Key>, String> {
+        self.inner.read_key()
+    }
+    fn var(&self, name: &str) -> Option<String> {
+        self.inner.var(name)
+    }
+    fn term_size(&self) -> Result<(usize, usize), String> {
+        self.inner.term_size()
+    }
+    fn term_move_cursor(&self, row: usize, col: usize) -> Result<(), String> {
+        self.inner.term_move_cursor(row, col)
+    }
+    fn term_clear(&self) -> Result<(), String> {
+        self.inner.term_clear()
+    }
+    fn term_save_cursor(&self) -> Result<(), String> {
+        self.inner.term_save_cursor()
+    }
+    fn term_restore_cursor(&self) -> Result<(), String> {
+        self.inner.term_restore_cursor()
+    }
+    fn term_set_alternate_screen(&self, on: bool) -> Result<(), String> {
+        self.inner.term_set_alternate_screen(on)
+    }
+    fn clipboard_get(&self) -> Result<String, String> {
+        self.inner.clipboard_get()
+    }
+    fn clipboard_set(&self, text: &str) -> Result<(), String> {
+        self.inner.clipboard_set(text)
+    }
+    fn file_exists(&self, path: &str) -> bool {
+        self.inner.file_exists(path)
+    }
+    fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
+        self.inner.list_dir(path)
+    }
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        self.inner.is_file(path)
+    }
+    fn canonicalize(&self, path: &str) -> Result<String, String> {
+        self.inner.canonicalize(path)
+    }
+    fn symlink(&self, target: &str, link: &str) -> Result<(), String> {
+        self.inner.symlink(target, link)
+    }
+    fn read_link(&self, path: &str) -> Result<String, String> {
+        self.inner.read_link(path)
+    }
+    fn delete(&self, path: &str) -> Result<(), String> {
+        self.inner.delete(path)
+    }
+    fn trash(&self, path: &str) -> Result<(), String> {
+        self.inner.trash(path)
+    }
+    fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
+        self.inner.read(handle, count)
+    }
+    fn read_exact(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
+        self.inner.read_exact(handle, count)
+    }
+    fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String> {
+        self.inner.read_until(handle, delim)
+    }
+    fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String> {
+        self.inner.write(handle, contents)
+    }
+    fn write_some(&self, handle: Handle, contents: &[u8]) -> Result<usize, String> {
+        self.inner.write_some(handle, contents)
+    }
+    fn create_file(&self, path: &Path) -> Result<Handle, String> {
+        self.inner.create_file(path)
+    }
+    fn temp_file(&self) -> Result<(String, Handle), String> {
+        self.inner.temp_file()
+    }
+    fn open_file(&self, path: &Path) -> Result<Handle, String> {
+        self.inner.open_file(path)
+    }
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        self.inner.file_read_all(path)
+    }
+    fn read_chunks(
+        &self,
+        path: &Path,
+        chunk_size: usize,
+        f: &mut dyn FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        self.inner.read_chunks(path, chunk_size, f)
+    }
+    fn is_binary_file(&self, path: &str) -> Result<bool, String> {
+        self.inner.is_binary_file(path)
+    }
+    fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        self.inner.file_write_all(path, contents)
+    }
+    fn write_atomic(&self, path: &str, contents: &[u8]) -> Result<(), String> {
+        self.inner.write_atomic(path, contents)
+    }
+    fn sleep(&self, seconds: f64) -> Result<(), String> {
+        self.inner.sleep(seconds)
+    }
+    #[cfg(feature = "image")]
+    fn show_image(&self, image: image::DynamicImage) -> Result<(), String> {
+        self.inner.show_image(image)
+    }
+    #[cfg(feature = "image")]
+    fn show_image_protocol(
+        &self,
+        image: image::DynamicImage,
+        protocol: ImageProtocol,
+    ) -> Result<(), String> {
+        self.inner.show_image_protocol(image, protocol)
+    }
+    #[cfg(feature = "image")]
+    fn save_image(&self, image: image::DynamicImage, path: &str) -> Result<(), String> {
+        self.inner.save_image(image, path)
+    }
+    fn show_gif(&self, gif_bytes: Vec<u8>) -> Result<(), String> {
+        self.inner.show_gif(gif_bytes)
+    }
+    fn play_audio(&self, wave_bytes: Vec<u8>) -> Result<(), String> {
+        self.inner.play_audio(wave_bytes)
+    }
+    fn audio_sample_rate(&self) -> u32 {
+        self.inner.audio_sample_rate()
+    }
+    fn stream_audio(&self, f: AudioStreamFn) -> Result<(), String> {
+        self.inner.stream_audio(f)
+    }
+    fn record_audio(&self, seconds: f64) -> Result<Vec<u8>, String> {
+        self.inner.record_audio(seconds)
+    }
+    fn tcp_listen(&self, addr: &str) -> Result<Handle, String> {
+        self.inner.tcp_listen(addr)
+    }
+    fn tcp_accept(&self, handle: Handle) -> Result<Handle, String> {
+        self.inner.tcp_accept(handle)
+    }
+    fn tcp_connect(&self, addr: &str) -> Result<Handle, String> {
+        self.inner.tcp_connect(addr)
+    }
+    fn tcp_connect_fast(&self, addr: &str, timeout: Duration) -> Result<Handle, String> {
+        self.inner.tcp_connect_fast(addr, timeout)
+    }
+    fn tcp_connect_from(&self, local: &str, remote: &str) -> Result<Handle, String> {
+        self.inner.tcp_connect_from(local, remote)
+    }
+    fn tcp_addr(&self, handle: Handle) -> Result<String, String> {
+        self.inner.tcp_addr(handle)
+    }
+    fn tcp_local_addr(&self, handle: Handle) -> Result<String, String> {
+        self.inner.tcp_local_addr(handle)
+    }
+    fn tcp_set_non_blocking(&self, handle: Handle, non_blocking: bool) -> Result<(), String> {
+        self.inner.tcp_set_non_blocking(handle, non_blocking)
+    }
+    fn tcp_set_read_timeout(
+        &self,
+        handle: Handle,
+        timeout: Option<Duration>,
+    ) -> Result<(), String> {
+        self.inner.tcp_set_read_timeout(handle, timeout)
+    }
+    fn tcp_set_write_timeout(
+        &self,
+        handle: Handle,
+        timeout: Option<Duration>,
+    ) -> Result<(), String> {
+        self.inner.tcp_set_write_timeout(handle, timeout)
+    }
+    fn handle_kind(&self, handle: Handle) -> Option<HandleKind> {
+        self.inner.handle_kind(handle)
+    }
+    fn handle_stats(&self, handle: Handle) -> Option<HandleStats> {
+        self.inner.handle_stats(handle)
+    }
+    fn live_handles(&self) -> Vec<(Handle, HandleKind)> {
+        self.inner.live_handles()
+    }
+    fn flush_all(&self) -> Result<(), String> {
+        self.inner.flush_all()
+    }
+    fn close(&self, handle: Handle) -> Result<(), String> {
+        self.inner.close(handle)
+    }
+    fn invoke(&self, path: &str) -> Result<(), String> {
+        self.inner.invoke(path)
+    }
+    fn run_command_inherit(&self, command: &str, args: &[&str]) -> Result<i32, String> {
+        self.inner.run_command_inherit(command, args)
+    }
+    fn run_command_capture(
+        &self,
+        command: &str,
+        args: &[&str],
+    ) -> Result<(i32, String, String), String> {
+        self.inner.run_command_capture(command, args)
+    }
+    fn run_command_capture_timeout(
+        &self,
+        command: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> Result<(i32, String, String), String> {
+        self.inner
+            .run_command_capture_timeout(command, args, timeout)
+    }
+    fn command_exists(&self, command: &str) -> bool {
+        self.inner.command_exists(command)
+    }
+    fn change_directory(&self, path: &str) -> Result<(), String> {
+        self.inner.change_directory(path)
+    }
+    fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
+        self.inner.https_get(request, handle)
+    }
+    fn https_get_bytes(&self, request: &str, handle: Handle) -> Result<Vec<u8>, String> {
+        self.inner.https_get_bytes(request, handle)
+    }
+    fn https_add_root_cert(&self, pem: &[u8]) -> Result<(), String> {
+        self.inner.https_add_root_cert(pem)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemSys, Uiua};
+
+    #[test]
+    fn capture_output() {
+        let mut uiua = Uiua::with_backend(CapturingSys::new(MemSys::new()));
+        let code = "&p \"hello\"\n&pf \"wor\"\n&w \"ld\" 2";
+        uiua.run_str(code).unwrap();
+        let sys = uiua.downcast_backend::<CapturingSys<MemSys>>().unwrap();
+        assert_eq!(sys.take_stdout(), "hello\nwor");
+        assert_eq!(sys.take_stderr(), "ld");
+        assert_eq!(sys.take_stdout(), "");
+    }
+}
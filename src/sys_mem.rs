@@ -0,0 +1,268 @@
+use std::{
+    any::Any,
+    path::Path,
+    sync::atomic::{self, AtomicU64},
+};
+
+use dashmap::DashMap;
+
+use crate::{normalize_path, Handle, SysBackend};
+
+/// A system backend with an in-memory filesystem
+///
+/// Files are stored in memory and never touch the disk, which makes it useful for testing
+/// programs that manipulate files. Directories exist implicitly as long as they contain a file.
+///
+/// All non-filesystem operations are unsupported.
+pub struct MemSys {
+    next_handle: AtomicU64,
+    files: DashMap<String, Vec<u8>>,
+    open_files: DashMap<Handle, OpenFile>,
+}
+
+struct OpenFile {
+    path: String,
+    cursor: usize,
+}
+
+impl Default for MemSys {
+    fn default() -> Self {
+        Self {
+            next_handle: Handle::FIRST_UNRESERVED.0.into(),
+            files: DashMap::new(),
+            open_files: DashMap::new(),
+        }
+    }
+}
+
+impl MemSys {
+    /// Create a new empty in-memory filesystem
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn key(path: impl AsRef<Path>) -> String {
+        normalize_path(&path.as_ref().to_string_lossy())
+    }
+    fn is_dir(&self, key: &str) -> bool {
+        key == "."
+            || self
+                .files
+                .iter()
+                .any(|file| Self::is_inside(file.key(), key))
+    }
+    fn is_inside(file: &str, dir: &str) -> bool {
+        Path::new(file)
+            .ancestors()
+            .skip(1)
+            .any(|ancestor| Self::key(ancestor) == dir)
+    }
+    fn parent(key: &str) -> String {
+        Self::key(Path::new(key).parent().unwrap_or(Path::new("")))
+    }
+    fn open(&self, key: String) -> Handle {
+        let handle = Handle(self.next_handle.fetch_add(1, atomic::Ordering::Relaxed));
+        let file = OpenFile {
+            path: key,
+            cursor: 0,
+        };
+        self.open_files.insert(handle, file);
+        handle
+    }
+}
+
+impl SysBackend for MemSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn file_exists(&self, path: &str) -> bool {
+        let key = Self::key(path);
+        self.files.contains_key(&key) || self.is_dir(&key)
+    }
+    fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
+        let key = Self::key(path);
+        if !self.is_dir(&key) {
+            return Err(format!("No such directory: {path}"));
+        }
+        let mut paths: Vec<String> = Vec::new();
+        for file in self.files.iter() {
+            // Find the ancestor of the file that is directly inside the directory
+            let child = Path::new(file.key()).ancestors().find_map(|ancestor| {
+                let name = ancestor.file_name()?;
+                (Self::parent(&ancestor.to_string_lossy()) == key).then_some(name)
+            });
+            if let Some(name) = child {
+                // Always join with `/` so the listing is the same on every platform
+                let name = name.to_string_lossy();
+                let child = match path {
+                    "" => name.into_owned(),
+                    path if path.ends_with('/') => format!("{path}{name}"),
+                    path => format!("{path}/{name}"),
+                };
+                if !paths.contains(&child) {
+                    paths.push(child);
+                }
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        let key = Self::key(path);
+        if self.files.contains_key(&key) {
+            Ok(true)
+        } else if self.is_dir(&key) {
+            Ok(false)
+        } else {
+            Err(format!("No such file or directory: {path}"))
+        }
+    }
+    fn delete(&self, path: &str) -> Result<(), String> {
+        let key = Self::key(path);
+        if self.files.remove(&key).is_some() {
+            return Ok(());
+        }
+        if !self.is_dir(&key) || key == "." {
+            return Err(format!("No such file or directory: {path}"));
+        }
+        self.files.retain(|file, _| !Self::is_inside(file, &key));
+        Ok(())
+    }
+    fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
+        let mut open = self
+            .open_files
+            .get_mut(&handle)
+            .ok_or("Invalid file handle")?;
+        let file = self
+            .files
+            .get(&open.path)
+            .ok_or("File was deleted while open")?;
+        let start = open.cursor.min(file.len());
+        let end = start.saturating_add(count).min(file.len());
+        let bytes = file[start..end].to_vec();
+        open.cursor = end;
+        Ok(bytes)
+    }
+    fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String> {
+        let mut open = self
+            .open_files
+            .get_mut(&handle)
+            .ok_or("Invalid file handle")?;
+        let mut file = self
+            .files
+            .get_mut(&open.path)
+            .ok_or("File was deleted while open")?;
+        let start = open.cursor.min(file.len());
+        let end = start + contents.len();
+        if end > file.len() {
+            file.resize(end, 0);
+        }
+        file[start..end].copy_from_slice(contents);
+        open.cursor = end;
+        Ok(())
+    }
+    fn create_file(&self, path: &Path) -> Result<Handle, String> {
+        let key = Self::key(path);
+        if self.is_dir(&key) {
+            return Err(format!("{} is a directory", path.display()));
+        }
+        self.files.insert(key.clone(), Vec::new());
+        Ok(self.open(key))
+    }
+    fn open_file(&self, path: &Path) -> Result<Handle, String> {
+        let key = Self::key(path);
+        if !self.files.contains_key(&key) {
+            return Err(format!("No such file: {}", path.display()));
+        }
+        Ok(self.open(key))
+    }
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        let file = self.files.get(&Self::key(path));
+        let file = file.ok_or_else(|| format!("No such file: {}", path.display()))?;
+        Ok(file.clone())
+    }
+    fn close(&self, handle: Handle) -> Result<(), String> {
+        self.open_files
+            .remove(&handle)
+            .map(drop)
+            .ok_or_else(|| "Invalid file handle".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Uiua;
+
+    #[test]
+    fn write_and_read_back() {
+        let sys = MemSys::new();
+        sys.file_write_all("dir/a.txt".as_ref(), b"hello").unwrap();
+        sys.file_write_all("dir/sub/b.txt".as_ref(), b"world")
+            .unwrap();
+        assert_eq!(sys.file_read_all("./dir/a.txt".as_ref()).unwrap(), b"hello");
+        assert!(sys.file_exists("dir/sub"));
+        assert!(!sys.is_file("dir").unwrap());
+        assert_eq!(sys.list_dir("dir").unwrap(), ["dir/a.txt", "dir/sub"]);
+        assert_eq!(sys.list_dir("dir/").unwrap(), ["dir/a.txt", "dir/sub"]);
+        sys.delete("dir/sub").unwrap();
+        assert!(!sys.file_exists("dir/sub/b.txt"));
+        assert!(sys.file_exists("dir/a.txt"));
+    }
+
+    #[test]
+    fn read_in_chunks() {
+        let sys = MemSys::new();
+        let path = Path::new("data.bin");
+        sys.file_write_all(path, &(0..=100).collect::<Vec<u8>>())
+            .unwrap();
+        let mut sum = 0;
+        let mut chunk_lens = Vec::new();
+        sys.read_chunks(path, 30, &mut |chunk| {
+            sum += chunk.iter().map(|&b| b as u32).sum::<u32>();
+            chunk_lens.push(chunk.len());
+            Ok(())
+        })
+        .unwrap();
+        let all = sys.file_read_all(path).unwrap();
+        assert_eq!(sum, all.iter().map(|&b| b as u32).sum::<u32>());
+        assert_eq!(chunk_lens, [30, 30, 30, 11]);
+        // Stop early
+        let mut chunks = 0;
+        let res = sys.read_chunks(path, 30, &mut |_| {
+            chunks += 1;
+            Err("stop".into())
+        });
+        assert_eq!(res.unwrap_err(), "stop");
+        assert_eq!(chunks, 1);
+        assert!(sys.open_files.is_empty());
+    }
+
+    #[test]
+    fn binary_files() {
+        let sys = MemSys::new();
+        let files: [(&str, &[u8], bool); 4] = [
+            ("text.txt", "Hello, World!\n\tÜñíçøðé\r\n".as_bytes(), false),
+            ("empty.txt", b"", false),
+            ("nul.bin", b"abc\0def", true),
+            ("control.bin", b"\x01\x02\x03\x04abc", true),
+        ];
+        for (path, contents, binary) in files {
+            sys.file_write_all(path.as_ref(), contents).unwrap();
+            assert_eq!(sys.is_binary_file(path).unwrap(), binary, "{path}");
+        }
+        assert!(sys.is_binary_file("missing.txt").is_err());
+    }
+
+    #[test]
+    fn isolated_from_disk() {
+        let path = format!("uiua-mem-sys-test-{}.txt", std::process::id());
+        let mut uiua = Uiua::with_backend(MemSys::new());
+        uiua.run_str(&format!("&fwa \"{path}\" \"hello\"")).unwrap();
+        uiua.run_str(&format!("&fras \"{path}\"")).unwrap();
+        assert_eq!(uiua.pop_string().unwrap(), "hello");
+        assert!(!Path::new(&path).exists());
+    }
+}
@@ -0,0 +1,39 @@
+//! The open handle limit is global, so it is tested in its own process
+
+#![cfg(feature = "native_sys")]
+
+use std::{env, fs, process};
+
+use uiua::{set_max_open_handles, NativeSys, SysBackend};
+
+#[test]
+fn open_handle_limit() {
+    let path = env::temp_dir().join(format!("uiua-handle-limit-{}.txt", process::id()));
+    fs::write(&path, "hello").unwrap();
+    let temp_files = || {
+        let prefix = format!("uiua-{}-", process::id());
+        (fs::read_dir(env::temp_dir()).unwrap())
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().starts_with(&prefix)
+            })
+            .count()
+    };
+
+    set_max_open_handles(2);
+    let first = NativeSys.open_file(&path).unwrap();
+    let second = NativeSys.open_file(&path).unwrap();
+    let err = NativeSys.open_file(&path).unwrap_err();
+    assert!(err.contains("Too many open handles"), "{err}");
+    // Hitting the limit must not leave a temp file behind
+    let before = temp_files();
+    assert!(NativeSys.temp_file().is_err());
+    assert_eq!(temp_files(), before);
+
+    NativeSys.close(first).unwrap();
+    let third = NativeSys.open_file(&path).unwrap();
+    for handle in [second, third] {
+        NativeSys.close(handle).unwrap();
+    }
+    fs::remove_file(&path).unwrap();
+}